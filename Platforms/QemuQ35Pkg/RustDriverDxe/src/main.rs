@@ -30,6 +30,7 @@ pub extern "efiapi" fn efi_main(
 #[panic_handler]
 fn panic(info: &PanicInfo) -> ! {
     println!("{}", info);
+    serial_print_dxe::flush();
     loop {}
 }
 
@@ -0,0 +1,134 @@
+//! Buffered Writer
+//!
+//! Implements a line-buffered [`fmt::Write`] adapter used to batch serial output before it reaches the port.
+//!
+//! Output is held until a newline is written, the buffer fills, or [`BufferedWriter::flush`] is called. Anything
+//! still buffered when the system halts is lost, so callers must flush before halting.
+//!
+//! ## License
+//!
+//! Copyright (C) Microsoft Corporation. All rights reserved.
+//!
+//! SPDX-License-Identifier: BSD-2-Clause-Patent
+//!
+use core::{fmt, str};
+
+/// Default number of bytes held before output is pushed to the underlying writer.
+pub const DEFAULT_BUFFER_SIZE: usize = 64;
+
+/// A line-buffered wrapper around a [`fmt::Write`] implementation.
+pub struct BufferedWriter<W: fmt::Write, const N: usize = DEFAULT_BUFFER_SIZE> {
+    inner: W,
+    buffer: [u8; N],
+    len: usize,
+}
+
+impl<W: fmt::Write, const N: usize> BufferedWriter<W, N> {
+    pub const fn new(inner: W) -> Self {
+        Self { inner, buffer: [0; N], len: 0 }
+    }
+
+    /// Returns the number of bytes currently buffered.
+    pub fn buffered_len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns a mutable reference to the underlying writer.
+    ///
+    /// Writing to the underlying writer directly bypasses any output that is still buffered.
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.inner
+    }
+
+    /// Drains any buffered output to the underlying writer.
+    pub fn flush(&mut self) -> fmt::Result {
+        if self.len == 0 {
+            return Ok(());
+        }
+
+        // The buffer only ever holds whole `&str` pieces, so it is always valid UTF-8.
+        let pending = str::from_utf8(&self.buffer[..self.len]).map_err(|_| fmt::Error)?;
+        let result = self.inner.write_str(pending);
+        self.len = 0;
+        result
+    }
+}
+
+impl<W: fmt::Write, const N: usize> fmt::Write for BufferedWriter<W, N> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        for piece in s.split_inclusive('\n') {
+            if self.len + piece.len() > N {
+                self.flush()?;
+            }
+
+            if piece.len() > N {
+                self.inner.write_str(piece)?;
+                continue;
+            }
+
+            self.buffer[self.len..self.len + piece.len()].copy_from_slice(piece.as_bytes());
+            self.len += piece.len();
+
+            if piece.ends_with('\n') {
+                self.flush()?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate alloc;
+
+    use super::BufferedWriter;
+    use alloc::string::String;
+    use core::fmt::Write;
+
+    #[test]
+    fn test_output_is_held_until_flush() {
+        let mut writer = BufferedWriter::<String, 16>::new(String::new());
+
+        write!(writer, "partial {}", 42).unwrap();
+        assert_eq!(writer.get_mut().as_str(), "");
+        assert_eq!(writer.buffered_len(), 10);
+
+        writer.flush().unwrap();
+        assert_eq!(writer.get_mut().as_str(), "partial 42");
+        assert_eq!(writer.buffered_len(), 0);
+    }
+
+    #[test]
+    fn test_newline_pushes_completed_lines() {
+        let mut writer = BufferedWriter::<String, 16>::new(String::new());
+
+        writer.write_str("one\ntwo").unwrap();
+        assert_eq!(writer.get_mut().as_str(), "one\n");
+
+        writer.flush().unwrap();
+        assert_eq!(writer.get_mut().as_str(), "one\ntwo");
+    }
+
+    #[test]
+    fn test_full_buffer_is_pushed_before_overflow() {
+        let mut writer = BufferedWriter::<String, 8>::new(String::new());
+
+        writer.write_str("abcdef").unwrap();
+        writer.write_str("ghij").unwrap();
+        assert_eq!(writer.get_mut().as_str(), "abcdef");
+
+        writer.write_str("a piece longer than the buffer").unwrap();
+        assert_eq!(writer.get_mut().as_str(), "abcdefghija piece longer than the buffer");
+        assert_eq!(writer.buffered_len(), 0);
+    }
+
+    #[test]
+    fn test_multibyte_characters_are_not_split() {
+        let mut writer = BufferedWriter::<String, 8>::new(String::new());
+
+        writer.write_str("abcdeé").unwrap();
+        writer.write_str("ü").unwrap();
+        writer.flush().unwrap();
+        assert_eq!(writer.get_mut().as_str(), "abcdeéü");
+    }
+}
@@ -2,6 +2,9 @@
 //!
 //! Implements print! and prinln! macro support for serial port printing.
 //!
+//! Also provides error!, warn!, info!, debug!, trace! macros filtered by the level set with
+//! [`log_level::set_max_level`].
+//!
 //! ## License
 //!
 //! Copyright (C) Microsoft Corporation. All rights reserved.
@@ -9,17 +12,17 @@
 //! SPDX-License-Identifier: BSD-2-Clause-Patent
 //!
 #![no_std]
-#[cfg(not(feature = "std"))]
+pub mod buffered_writer;
+pub mod log_level;
+pub mod ring_buffer;
 pub mod serial_port_print;
 
-#[cfg(feature = "std")]
-extern crate std;
+pub use serial_port_print::flush;
 
 #[cfg(feature = "std")]
-pub use {std::print, std::println};
+extern crate std;
 
-#[cfg(not(feature = "std"))]
-mod no_std_debug {
+mod debug_print {
     #[macro_export]
     macro_rules! print {
     ($fmt:expr) => ($crate::serial_print!($fmt));
@@ -28,6 +31,7 @@ mod no_std_debug {
 
     #[macro_export]
     macro_rules! println {
+    () => ($crate::serial_println!());
     ($fmt:expr) => ($crate::serial_println!($fmt));
     ($fmt:expr, $($arg:tt)*) => ($crate::serial_println!($fmt, $($arg)*));
   }
@@ -36,10 +40,34 @@ mod no_std_debug {
 #[cfg(test)]
 mod tests {
 
-    use crate::print;
+    use crate::{
+        print,
+        serial_port_print::{
+            set_buffered_output,
+            test_port::{port_output, TEST_LOCK},
+        },
+    };
 
     #[test]
     fn test_print() {
+        let _guard = TEST_LOCK.lock();
         print!("This is a test");
+        assert!(port_output().ends_with("This is a test"));
+    }
+
+    #[test]
+    fn test_flush() {
+        let _guard = TEST_LOCK.lock();
+
+        set_buffered_output(true);
+        print!("This is a partial line");
+        assert!(!port_output().contains("This is a partial line"));
+
+        crate::flush();
+        assert!(port_output().ends_with("This is a partial line"));
+
+        print!("Pending when buffering is disabled");
+        set_buffered_output(false);
+        assert!(port_output().ends_with("Pending when buffering is disabled"));
     }
 }
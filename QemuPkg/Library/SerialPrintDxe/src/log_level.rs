@@ -0,0 +1,136 @@
+//! Log Level
+//!
+//! Implements error!, warn!, info!, debug!, trace! macros filtered by a runtime-settable global level.
+//!
+//! Messages above the current level return before their arguments are formatted, so raising the threshold cuts serial
+//! traffic without recompiling.
+//!
+//! ## License
+//!
+//! Copyright (C) Microsoft Corporation. All rights reserved.
+//!
+//! SPDX-License-Identifier: BSD-2-Clause-Patent
+//!
+use core::sync::atomic::{AtomicU8, Ordering};
+
+/// Verbosity of a log message, from least to most verbose.
+///
+/// As a threshold, [`Level::Off`] suppresses all messages.
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+    Off   = 0,
+    Error = 1,
+    Warn  = 2,
+    Info  = 3,
+    Debug = 4,
+    Trace = 5,
+}
+
+impl Level {
+    const fn from_u8(value: u8) -> Self {
+        match value {
+            0 => Level::Off,
+            1 => Level::Error,
+            2 => Level::Warn,
+            3 => Level::Info,
+            4 => Level::Debug,
+            _ => Level::Trace,
+        }
+    }
+}
+
+static MAX_LEVEL: AtomicU8 = AtomicU8::new(Level::Trace as u8);
+
+/// Sets the most verbose level that is printed. Defaults to [`Level::Trace`].
+pub fn set_max_level(level: Level) {
+    MAX_LEVEL.store(level as u8, Ordering::Relaxed);
+}
+
+/// Returns the most verbose level that is printed.
+pub fn max_level() -> Level {
+    Level::from_u8(MAX_LEVEL.load(Ordering::Relaxed))
+}
+
+/// Returns true if messages at `level` are printed.
+#[inline]
+pub fn enabled(level: Level) -> bool {
+    level != Level::Off && level as u8 <= MAX_LEVEL.load(Ordering::Relaxed)
+}
+
+/// Prints a line at the given level if it is enabled.
+#[macro_export]
+macro_rules! log {
+    ($level:expr, $($arg:tt)*) => {
+        if $crate::log_level::enabled($level) {
+            $crate::println!($($arg)*);
+        }
+    };
+}
+
+/// Prints a line at [`Level::Error`](crate::log_level::Level::Error).
+#[macro_export]
+macro_rules! error {
+    ($($arg:tt)*) => ($crate::log!($crate::log_level::Level::Error, $($arg)*));
+}
+
+/// Prints a line at [`Level::Warn`](crate::log_level::Level::Warn).
+#[macro_export]
+macro_rules! warn {
+    ($($arg:tt)*) => ($crate::log!($crate::log_level::Level::Warn, $($arg)*));
+}
+
+/// Prints a line at [`Level::Info`](crate::log_level::Level::Info).
+#[macro_export]
+macro_rules! info {
+    ($($arg:tt)*) => ($crate::log!($crate::log_level::Level::Info, $($arg)*));
+}
+
+/// Prints a line at [`Level::Debug`](crate::log_level::Level::Debug).
+#[macro_export]
+macro_rules! debug {
+    ($($arg:tt)*) => ($crate::log!($crate::log_level::Level::Debug, $($arg)*));
+}
+
+/// Prints a line at [`Level::Trace`](crate::log_level::Level::Trace).
+#[macro_export]
+macro_rules! trace {
+    ($($arg:tt)*) => ($crate::log!($crate::log_level::Level::Trace, $($arg)*));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{enabled, max_level, set_max_level, Level};
+    use crate::serial_port_print::test_port::{port_output, TEST_LOCK};
+    use core::cell::Cell;
+
+    #[test]
+    fn test_level_filtering() {
+        let _guard = TEST_LOCK.lock();
+        set_max_level(Level::Warn);
+        assert_eq!(max_level(), Level::Warn);
+        assert!(enabled(Level::Error));
+        assert!(enabled(Level::Warn));
+        assert!(!enabled(Level::Info));
+        assert!(!enabled(Level::Off));
+
+        // Arguments of a filtered message must not be evaluated.
+        let evaluated = Cell::new(false);
+        let mark = || {
+            evaluated.set(true);
+            0
+        };
+        crate::debug!("filtered {}", mark());
+        assert!(!evaluated.get());
+        assert!(!port_output().contains("filtered"));
+        crate::error!("printed {}", mark());
+        assert!(evaluated.get());
+        assert!(port_output().ends_with("printed 0\n"));
+
+        set_max_level(Level::Off);
+        assert!(!enabled(Level::Error));
+
+        set_max_level(Level::Trace);
+        assert!(enabled(Level::Trace));
+    }
+}
@@ -0,0 +1,207 @@
+//! Ring Buffer
+//!
+//! Implements a fixed-capacity byte ring buffer that retains the most recently written bytes. Used to keep the tail of
+//! serial output in memory for post-mortem retrieval.
+//!
+//! ## License
+//!
+//! Copyright (C) Microsoft Corporation. All rights reserved.
+//!
+//! SPDX-License-Identifier: BSD-2-Clause-Patent
+//!
+
+/// A byte ring buffer over caller-provided storage.
+///
+/// Once the buffer is full, each write discards the oldest bytes so that only the most recent `capacity()` bytes are
+/// retained.
+pub struct RingBuffer<S> {
+    storage: S,
+    start: usize,
+    len: usize,
+}
+
+impl<S> RingBuffer<S> {
+    pub const fn new(storage: S) -> Self {
+        Self { storage, start: 0, len: 0 }
+    }
+}
+
+impl<S: AsRef<[u8]> + AsMut<[u8]>> RingBuffer<S> {
+    /// Returns the number of bytes the buffer can retain.
+    pub fn capacity(&self) -> usize {
+        self.storage.as_ref().len()
+    }
+
+    /// Returns the number of bytes currently retained.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns true if no bytes are retained.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Discards all retained bytes.
+    pub fn clear(&mut self) {
+        self.start = 0;
+        self.len = 0;
+    }
+
+    /// Appends `bytes`, discarding the oldest retained bytes if the buffer overflows.
+    pub fn write(&mut self, bytes: &[u8]) {
+        let capacity = self.capacity();
+        if capacity == 0 {
+            return;
+        }
+
+        // Only the last `capacity` bytes of an oversized write can survive.
+        if bytes.len() >= capacity {
+            self.storage.as_mut().copy_from_slice(&bytes[bytes.len() - capacity..]);
+            self.start = 0;
+            self.len = capacity;
+            return;
+        }
+
+        let end = (self.start + self.len) % capacity;
+        let first = bytes.len().min(capacity - end);
+        let storage = self.storage.as_mut();
+        storage[end..end + first].copy_from_slice(&bytes[..first]);
+        storage[..bytes.len() - first].copy_from_slice(&bytes[first..]);
+
+        let len = self.len + bytes.len();
+        if len > capacity {
+            self.start = (self.start + len - capacity) % capacity;
+            self.len = capacity;
+        } else {
+            self.len = len;
+        }
+    }
+
+    /// Copies the most recent retained bytes into `out`, oldest first, without consuming them.
+    ///
+    /// If `out` is smaller than the retained contents, only the newest `out.len()` bytes are copied. Returns the
+    /// number of bytes copied.
+    pub fn copy_to(&self, out: &mut [u8]) -> usize {
+        let count = self.len.min(out.len());
+        let offset = (self.start + self.len - count) % self.capacity().max(1);
+        self.copy_from(offset, &mut out[..count]);
+        count
+    }
+
+    /// Moves the oldest retained bytes into `out`, removing them from the buffer.
+    ///
+    /// Repeated calls return the retained contents in order. Returns the number of bytes moved.
+    pub fn drain_to(&mut self, out: &mut [u8]) -> usize {
+        let count = self.len.min(out.len());
+        self.copy_from(self.start, &mut out[..count]);
+        if count == self.len {
+            self.clear();
+        } else {
+            self.start = (self.start + count) % self.capacity();
+            self.len -= count;
+        }
+        count
+    }
+
+    fn copy_from(&self, offset: usize, out: &mut [u8]) {
+        let storage = self.storage.as_ref();
+        let first = out.len().min(storage.len() - offset);
+        out[..first].copy_from_slice(&storage[offset..offset + first]);
+        let remaining = out.len() - first;
+        out[first..].copy_from_slice(&storage[..remaining]);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RingBuffer;
+
+    #[test]
+    fn test_write_within_capacity() {
+        let mut ring = RingBuffer::new([0u8; 8]);
+        ring.write(b"abc");
+        ring.write(b"de");
+
+        let mut out = [0u8; 8];
+        assert_eq!(ring.copy_to(&mut out), 5);
+        assert_eq!(&out[..5], b"abcde");
+        assert_eq!(ring.len(), 5);
+    }
+
+    #[test]
+    fn test_only_tail_is_retained_after_wraparound() {
+        let mut ring = RingBuffer::new([0u8; 8]);
+        for chunk in [&b"012"[..], b"345", b"678", b"9ab"] {
+            ring.write(chunk);
+        }
+
+        let mut out = [0u8; 16];
+        assert_eq!(ring.copy_to(&mut out), 8);
+        assert_eq!(&out[..8], b"456789ab");
+    }
+
+    #[test]
+    fn test_oversized_write_keeps_tail() {
+        let mut ring = RingBuffer::new([0u8; 4]);
+        ring.write(b"x");
+        ring.write(b"0123456789");
+
+        let mut out = [0u8; 4];
+        assert_eq!(ring.copy_to(&mut out), 4);
+        assert_eq!(&out, b"6789");
+    }
+
+    #[test]
+    fn test_copy_to_short_slice_returns_newest_bytes() {
+        let mut ring = RingBuffer::new([0u8; 8]);
+        ring.write(b"0123456");
+        ring.write(b"789");
+
+        let mut out = [0u8; 3];
+        assert_eq!(ring.copy_to(&mut out), 3);
+        assert_eq!(&out, b"789");
+    }
+
+    #[test]
+    fn test_drain_consumes_oldest_first() {
+        let mut ring = RingBuffer::new([0u8; 8]);
+        ring.write(b"0123456");
+        ring.write(b"789");
+
+        let mut out = [0u8; 3];
+        assert_eq!(ring.drain_to(&mut out), 3);
+        assert_eq!(&out, b"234");
+        assert_eq!(ring.len(), 5);
+
+        ring.write(b"ab");
+        let mut rest = [0u8; 16];
+        assert_eq!(ring.drain_to(&mut rest), 7);
+        assert_eq!(&rest[..7], b"56789ab");
+        assert!(ring.is_empty());
+        assert_eq!(ring.drain_to(&mut rest), 0);
+    }
+
+    #[test]
+    fn test_borrowed_storage() {
+        let mut storage = [0u8; 4];
+        let mut ring = RingBuffer::new(&mut storage[..]);
+        ring.write(b"abcdef");
+
+        let mut out = [0u8; 4];
+        assert_eq!(ring.drain_to(&mut out), 4);
+        assert_eq!(&out, b"cdef");
+    }
+
+    #[test]
+    fn test_clear_and_empty_storage() {
+        let mut ring = RingBuffer::new([0u8; 4]);
+        ring.write(b"abc");
+        ring.clear();
+        assert!(ring.is_empty());
+
+        let mut empty = RingBuffer::new([0u8; 0]);
+        empty.write(b"abc");
+        assert_eq!(empty.copy_to(&mut [0u8; 4]), 0);
+    }
+}
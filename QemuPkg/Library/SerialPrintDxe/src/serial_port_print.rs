@@ -5,6 +5,15 @@
 //!     Uses hardcoded Serial ports for debug.
 //!     * Q35  -> base = 0x402
 //!     * Sbsa -> PL011 = 0x6000_0000 (PcdSerialRegisterBase)
+//!     With the `std` feature, output goes to the host stdout instead.
+//!
+//! Output is written through to the port on every print by default. When buffering is enabled with
+//! [`set_buffered_output`], output is held until a newline or a full buffer, and [`flush`] must be called before halting
+//! so that a trailing partial line is not lost.
+//!
+//! A caller-provided buffer registered with [`set_memory_log_buffer`] receives a copy of all output while tee mode is
+//! enabled, so that a later phase can retrieve it with [`capture_log`] or [`drain_memory_log`] even when no serial
+//! capture is attached.
 //!
 //! ## License
 //!
@@ -12,15 +21,108 @@
 //!
 //! SPDX-License-Identifier: BSD-2-Clause-Patent
 //!
-#[cfg(target_arch = "x86_64")]
+use crate::{buffered_writer::BufferedWriter, ring_buffer::RingBuffer};
+use core::{
+    fmt,
+    sync::atomic::{AtomicBool, Ordering},
+};
+use spin::Mutex;
+
+#[cfg(all(not(feature = "std"), target_arch = "x86_64"))]
 pub mod x86_serial_port;
-#[cfg(target_arch = "x86_64")]
-pub use x86_serial_port::_print;
+#[cfg(all(not(feature = "std"), not(test), target_arch = "x86_64"))]
+pub use x86_serial_port::{_print, flush};
 
-#[cfg(target_arch = "aarch64")]
+#[cfg(all(not(feature = "std"), target_arch = "aarch64"))]
 pub mod aarch64_serial_port;
-#[cfg(target_arch = "aarch64")]
-pub use aarch64_serial_port::_print;
+#[cfg(all(not(feature = "std"), not(test), target_arch = "aarch64"))]
+pub use aarch64_serial_port::{_print, flush};
+
+#[cfg(feature = "std")]
+pub mod std_port;
+#[cfg(all(feature = "std", not(test)))]
+pub use std_port::{_print, flush};
+
+#[cfg(test)]
+pub(crate) mod test_port;
+#[cfg(test)]
+pub use test_port::{_print, flush};
+
+static BUFFERED_OUTPUT: AtomicBool = AtomicBool::new(false);
+
+/// Enables or disables buffering of output between prints.
+///
+/// While enabled, output is only written to the port on a newline, when the buffer fills, or on [`flush`]. Callers that
+/// enable buffering must call [`flush`] before halting. Disabling buffering flushes any pending output.
+pub fn set_buffered_output(enabled: bool) {
+    BUFFERED_OUTPUT.store(enabled, Ordering::Relaxed);
+    if !enabled {
+        flush();
+    }
+}
+
+/// Writes `args` to `port`, holding it in the buffer only if buffered output is enabled.
+fn write_output<W: fmt::Write>(port: &mut BufferedWriter<W>, args: fmt::Arguments) -> fmt::Result {
+    use core::fmt::Write;
+
+    if BUFFERED_OUTPUT.load(Ordering::Relaxed) {
+        TeeWriter(port).write_fmt(args)
+    } else {
+        port.flush()?;
+        TeeWriter(port.get_mut()).write_fmt(args)
+    }
+}
+
+static TEE_ENABLED: AtomicBool = AtomicBool::new(false);
+static MEMORY_LOG: Mutex<Option<RingBuffer<&'static mut [u8]>>> = Mutex::new(None);
+
+/// Registers `buf` as the ring buffer that retains serial output and enables tee mode.
+///
+/// Once `buf` is full, the oldest bytes are overwritten. Registering a new buffer discards the previous one along with
+/// any output it still holds.
+pub fn set_memory_log_buffer(buf: &'static mut [u8]) {
+    *MEMORY_LOG.lock() = Some(RingBuffer::new(buf));
+    TEE_ENABLED.store(true, Ordering::Relaxed);
+}
+
+/// Enables or disables tee mode.
+///
+/// While enabled, everything printed is also written to the buffer registered with [`set_memory_log_buffer`]. Output
+/// printed while tee mode is disabled, or before a buffer is registered, is not captured.
+pub fn set_tee_mode(enabled: bool) {
+    TEE_ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+/// Copies the output retained in the memory log buffer into `out`, oldest first, without consuming it.
+///
+/// If `out` is smaller than the retained output, only the most recent `out.len()` bytes are copied. Returns the number
+/// of bytes copied, which is zero if no memory log buffer is registered.
+pub fn capture_log(out: &mut [u8]) -> usize {
+    MEMORY_LOG.lock().as_ref().map_or(0, |log| log.copy_to(out))
+}
+
+/// Moves the oldest output held in the memory log buffer into `out`.
+///
+/// Drained bytes are removed from the memory log, so repeated calls return the log in order. Returns the number of
+/// bytes copied, which is zero if no memory log buffer is registered.
+pub fn drain_memory_log(out: &mut [u8]) -> usize {
+    MEMORY_LOG.lock().as_mut().map_or(0, |log| log.drain_to(out))
+}
+
+/// A [`fmt::Write`] adapter that records output in the memory log buffer before forwarding it.
+struct TeeWriter<'a, W: fmt::Write>(&'a mut W);
+
+impl<W: fmt::Write> fmt::Write for TeeWriter<'_, W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        if TEE_ENABLED.load(Ordering::Relaxed) {
+            // Skip capture rather than deadlock if output is printed while the log is being read.
+            if let Some(log) = MEMORY_LOG.try_lock().as_mut().and_then(|log| log.as_mut()) {
+                log.write(s.as_bytes());
+            }
+        }
+        self.0.write_str(s)
+    }
+}
 
 /// Prints to the host through the serial interface.
 #[macro_export]
@@ -38,3 +140,71 @@ macro_rules! serial_println {
   ($fmt:expr, $($arg:tt)*) => ($crate::serial_print!(
     concat!($fmt, "\n"), $($arg)*));
 }
+
+#[cfg(test)]
+mod tests {
+    extern crate alloc;
+
+    use super::{capture_log, drain_memory_log, set_memory_log_buffer, set_tee_mode, test_port::TEST_LOCK, TeeWriter};
+    use alloc::{boxed::Box, string::String};
+    use core::fmt::Write;
+
+    #[test]
+    fn test_tee_mode_captures_output() {
+        let _guard = TEST_LOCK.lock();
+        let mut port = String::new();
+        let mut out = [0u8; 64];
+
+        set_memory_log_buffer(Box::leak(Box::new([0u8; 32])));
+        set_tee_mode(false);
+        TeeWriter(&mut port).write_str("not captured ").unwrap();
+        set_tee_mode(true);
+        write!(TeeWriter(&mut port), "captured {}", 1).unwrap();
+        assert_eq!(port, "not captured captured 1");
+
+        // Capturing leaves the log in place for a later drain of the same buffer.
+        let count = capture_log(&mut out);
+        assert_eq!(&out[..count], b"captured 1");
+        assert_eq!(capture_log(&mut out), count);
+        assert_eq!(drain_memory_log(&mut out), count);
+        assert_eq!(capture_log(&mut out), 0);
+    }
+
+    #[test]
+    fn test_tee_mode_retains_only_the_tail() {
+        const LOG_SIZE: usize = 64;
+
+        let _guard = TEST_LOCK.lock();
+        set_memory_log_buffer(Box::leak(Box::new([0u8; LOG_SIZE])));
+
+        let mut expected = String::new();
+        for line in 0..20 {
+            crate::println!("tee line {}", line);
+            writeln!(expected, "tee line {}", line).unwrap();
+        }
+        assert!(expected.len() > LOG_SIZE);
+
+        let mut out = [0u8; 2 * LOG_SIZE];
+        let count = capture_log(&mut out);
+        assert_eq!(count, LOG_SIZE);
+        assert_eq!(&out[..count], &expected.as_bytes()[expected.len() - LOG_SIZE..]);
+    }
+
+    #[test]
+    fn test_memory_log_drains_output() {
+        let _guard = TEST_LOCK.lock();
+        let mut port = String::new();
+        let mut out = [0u8; 16];
+
+        TeeWriter(&mut port).write_str("dropped ").unwrap();
+        set_memory_log_buffer(Box::leak(Box::new([0u8; 8])));
+        TeeWriter(&mut port).write_str("0123456789").unwrap();
+
+        assert_eq!(drain_memory_log(&mut out[..4]), 4);
+        assert_eq!(&out[..4], b"2345");
+        assert_eq!(drain_memory_log(&mut out), 4);
+        assert_eq!(&out[..4], b"6789");
+        assert_eq!(drain_memory_log(&mut out), 0);
+        assert_eq!(port, "dropped 0123456789");
+    }
+}
@@ -10,6 +10,7 @@
 //!
 //! SPDX-License-Identifier: BSD-2-Clause-Patent
 //!
+use crate::buffered_writer::BufferedWriter;
 use core::{fmt, ptr};
 use lazy_static::lazy_static;
 use spin::Mutex;
@@ -51,27 +52,24 @@ impl fmt::Write for SerialPortHandle {
 }
 
 lazy_static! {
-  pub static ref UART0: Mutex<SerialPortHandle> = {
+  pub static ref UART0: Mutex<BufferedWriter<SerialPortHandle>> = {
     // 0x6000_0000 is the PL011 PcdSerialRegisterBase value
     let serial_port = SerialPortHandle::new(0x6000_0000 as *mut u8);
-    Mutex::new(serial_port)
+    Mutex::new(BufferedWriter::new(serial_port))
   };
 }
 
-#[cfg(not(test))]
 #[doc(hidden)]
 pub fn _print(args: ::core::fmt::Arguments) {
-    use core::fmt::Write;
-
-    UART0.lock().write_fmt(args).expect("Printing to serial failed");
+    super::write_output(&mut UART0.lock(), args).expect("Printing to serial failed");
 }
 
-#[cfg(test)]
-pub fn _print(args: ::core::fmt::Arguments) {
-    extern crate alloc;
-    use alloc::vec::Vec;
-
-    let mut vec = Vec::new();
-    vec.push(args.as_str());
-    assert_eq!(vec[0], args.as_str())
+/// Drains any buffered output to the serial port.
+///
+/// Must be called before halting when buffered output is enabled.
+pub fn flush() {
+    // Use try_lock so a panic raised while printing does not deadlock on the held port.
+    if let Some(mut uart) = UART0.try_lock() {
+        let _ = uart.flush();
+    }
 }
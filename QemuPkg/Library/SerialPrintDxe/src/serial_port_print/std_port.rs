@@ -0,0 +1,108 @@
+//! std port
+//!
+//! Implements a port that writes to the host stdout. Used in place of the serial port when the `std` feature is
+//! enabled.
+//!
+//! Output is forwarded through [`std::print!`] so that the test harness still captures it in host unit tests.
+//!
+//! ## License
+//!
+//! Copyright (C) Microsoft Corporation. All rights reserved.
+//!
+//! SPDX-License-Identifier: BSD-2-Clause-Patent
+//!
+use crate::buffered_writer::BufferedWriter;
+use core::fmt;
+use spin::Mutex;
+use std::io::{self, Write};
+
+/// A [`fmt::Write`] port over the host stdout.
+pub struct StdoutPort;
+
+impl fmt::Write for StdoutPort {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        std::print!("{}", s);
+        Ok(())
+    }
+}
+
+pub static STDOUT: Mutex<BufferedWriter<StdoutPort>> = Mutex::new(BufferedWriter::new(StdoutPort));
+
+#[doc(hidden)]
+pub fn _print(args: ::core::fmt::Arguments) {
+    print_to(&STDOUT, args);
+}
+
+/// Drains any buffered output to stdout.
+///
+/// Must be called before exiting so that buffered output is not lost.
+pub fn flush() {
+    flush_port(&STDOUT);
+    let _ = io::stdout().flush();
+}
+
+fn print_to<W: fmt::Write>(port: &Mutex<BufferedWriter<W>>, args: fmt::Arguments) {
+    super::write_output(&mut port.lock(), args).expect("Printing to stdout failed");
+}
+
+fn flush_port<W: fmt::Write>(port: &Mutex<BufferedWriter<W>>) {
+    if let Some(mut port) = port.try_lock() {
+        let _ = port.flush();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{flush_port, print_to};
+    use crate::{
+        buffered_writer::BufferedWriter,
+        serial_port_print::{set_buffered_output, test_port::TEST_LOCK},
+    };
+    use spin::Mutex;
+    use std::{env, process::Command, string::String};
+
+    const CAPTURE_MARKER: &str = "std port capture marker";
+
+    #[test]
+    fn test_buffered_output_is_written_on_flush() {
+        let _guard = TEST_LOCK.lock();
+        let port = Mutex::new(BufferedWriter::new(String::new()));
+
+        set_buffered_output(true);
+        print_to(&port, format_args!("partial {}", 1));
+        assert!(port.lock().get_mut().is_empty());
+
+        flush_port(&port);
+        set_buffered_output(false);
+        assert_eq!(port.lock().get_mut(), "partial 1");
+    }
+
+    /// Prints a partial line through the real stdout port. Run by [`test_stdout_port_is_captured`].
+    #[test]
+    #[ignore]
+    fn print_capture_marker() {
+        set_buffered_output(true);
+        super::_print(format_args!("{}", CAPTURE_MARKER));
+        super::flush();
+    }
+
+    #[test]
+    fn test_stdout_port_is_captured() {
+        let run = |nocapture: bool| {
+            let mut command = Command::new(env::current_exe().unwrap());
+            command.args(["--exact", "serial_port_print::std_port::tests::print_capture_marker", "--ignored"]);
+            if nocapture {
+                command.arg("--nocapture");
+            }
+            let output = command.output().unwrap();
+            assert!(output.status.success());
+            String::from_utf8(output.stdout).unwrap()
+        };
+
+        // Flushed output reaches stdout, but only when the harness is not capturing it.
+        assert!(run(true).contains(CAPTURE_MARKER));
+        let captured = run(false);
+        assert!(captured.contains("1 passed"));
+        assert!(!captured.contains(CAPTURE_MARKER));
+    }
+}
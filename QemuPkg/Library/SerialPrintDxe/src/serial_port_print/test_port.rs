@@ -0,0 +1,37 @@
+//! Test port
+//!
+//! Implements an in-memory port used in place of the serial port by unit tests, so tests can observe exactly what
+//! reaches the port.
+//!
+//! ## License
+//!
+//! Copyright (C) Microsoft Corporation. All rights reserved.
+//!
+//! SPDX-License-Identifier: BSD-2-Clause-Patent
+//!
+extern crate alloc;
+
+use super::write_output;
+use crate::buffered_writer::BufferedWriter;
+use alloc::string::String;
+use spin::Mutex;
+
+/// Serializes tests that print or touch the global output state.
+pub(crate) static TEST_LOCK: Mutex<()> = Mutex::new(());
+
+static TEST_PORT: Mutex<BufferedWriter<String>> = Mutex::new(BufferedWriter::new(String::new()));
+
+/// Returns a copy of everything that has reached the port.
+pub(crate) fn port_output() -> String {
+    TEST_PORT.lock().get_mut().clone()
+}
+
+#[doc(hidden)]
+pub fn _print(args: ::core::fmt::Arguments) {
+    write_output(&mut TEST_PORT.lock(), args).expect("Printing to test port failed");
+}
+
+/// Drains any buffered output to the test port.
+pub fn flush() {
+    let _ = TEST_PORT.lock().flush();
+}
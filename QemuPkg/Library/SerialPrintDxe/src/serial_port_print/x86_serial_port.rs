@@ -2,44 +2,72 @@
 //!
 //! Implements an x86_64 serial port instance. Used for debug prints in the QemuQ35Pkg.
 //!
+//! On Q35, port 0x402 is QEMU's isa-debugcon device rather than a 16550 UART. It accepts a byte on every write and has
+//! no line status register, so output is written without polling for transmitter readiness.
+//!
 //! ## License
 //!
 //! Copyright (C) Microsoft Corporation. All rights reserved.
 //!
 //! SPDX-License-Identifier: BSD-2-Clause-Patent
 //!
-use lazy_static::lazy_static;
+use crate::buffered_writer::BufferedWriter;
+use core::fmt;
 use spin::Mutex;
-use uart_16550::SerialPort;
-
-lazy_static! {
-    pub static ref SERIAL1: Mutex<SerialPort> = {
-        let mut serial_port = unsafe { SerialPort::new(0x402) };
-        serial_port.init();
-        Mutex::new(serial_port)
-    };
+use x86_64::instructions::port::PortWriteOnly;
+
+/// A port for QEMU's isa-debugcon device.
+pub struct DebugConPort {
+    data: PortWriteOnly<u8>,
+}
+
+impl DebugConPort {
+    /// Creates a port that writes to the debugcon device at `base`.
+    ///
+    /// ## Safety
+    ///
+    /// This function is unsafe because `base` must be the I/O port of a debugcon device, and writing to other I/O
+    /// ports could have side effects that violate memory safety.
+    pub const unsafe fn new(base: u16) -> Self {
+        Self { data: PortWriteOnly::new(base) }
+    }
+}
+
+impl fmt::Write for DebugConPort {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        for byte in s.bytes() {
+            unsafe {
+                self.data.write(byte);
+            }
+        }
+        Ok(())
+    }
 }
 
-#[cfg(not(test))]
+pub static SERIAL1: Mutex<BufferedWriter<DebugConPort>> =
+    Mutex::new(BufferedWriter::new(unsafe { DebugConPort::new(0x402) }));
+
 #[doc(hidden)]
 pub fn _print(args: ::core::fmt::Arguments) {
-    use core::fmt::Write;
     use x86_64::instructions::interrupts;
 
     interrupts::without_interrupts(|| {
         let serial_lock = SERIAL1.try_lock();
         if let Some(mut serial) = serial_lock {
-            serial.write_fmt(args).expect("Printing to serial failed");
+            super::write_output(&mut serial, args).expect("Printing to serial failed");
         }
     });
 }
 
-#[cfg(test)]
-pub fn _print(args: ::core::fmt::Arguments) {
-    extern crate alloc;
-    use alloc::vec::Vec;
+/// Drains any buffered output to the serial port.
+///
+/// Must be called before halting when buffered output is enabled.
+pub fn flush() {
+    use x86_64::instructions::interrupts;
 
-    let mut vec = Vec::new();
-    vec.push(args.as_str());
-    assert_eq!(vec[0], args.as_str())
+    interrupts::without_interrupts(|| {
+        if let Some(mut serial) = SERIAL1.try_lock() {
+            let _ = serial.flush();
+        }
+    });
 }